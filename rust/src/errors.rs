@@ -0,0 +1,39 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::PyErr;
+use std::fmt;
+
+/// Errors raised while resolving or validating a caller-supplied byte range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeError {
+    /// The range is internally inconsistent, e.g. `start` is after `end`.
+    InvalidRange { start: usize, end: usize },
+    /// A range endpoint falls outside the object's actual length.
+    RangeOutOfBounds { value: usize, file_length: usize },
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::InvalidRange { start, end } if start > end => {
+                write!(f, "invalid byte range: start ({start}) is after end ({end})")
+            }
+            RangeError::InvalidRange { start, end } => {
+                write!(f, "invalid byte range: start ({start}), end ({end})")
+            }
+            RangeError::RangeOutOfBounds { value, file_length } => {
+                write!(
+                    f,
+                    "byte range endpoint {value} is out of bounds for an object of length {file_length}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+impl From<RangeError> for PyErr {
+    fn from(err: RangeError) -> PyErr {
+        PyValueError::new_err(err.to_string())
+    }
+}
@@ -0,0 +1,76 @@
+use crate::store::{format_http_range, ObjectMeta, ObjectStore};
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::Client;
+use std::pin::Pin;
+use tokio_stream::{Stream, StreamExt};
+
+/// Generic HTTP(S) `ObjectStore`, for catalogs that expose plain range-request downloads
+/// rather than a provider-specific API. `bucket` is the scheme+host (e.g.
+/// `"https://example.org"`) and `key` is the path to the resource.
+pub struct HttpStore {
+    client: Client,
+}
+
+impl HttpStore {
+    pub fn new() -> Self {
+        Self { client: Client::new() }
+    }
+
+    fn url(bucket: &str, key: &str) -> String {
+        format!("{}/{}", bucket.trim_end_matches('/'), key.trim_start_matches('/'))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for HttpStore {
+    async fn fetch_range(&self, bucket: &str, key: &str, start: usize, end: usize) -> anyhow::Result<Bytes> {
+        let resp = self
+            .client
+            .get(Self::url(bucket, key))
+            .header("Range", format_http_range(start, end))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(resp.bytes().await?)
+    }
+
+    async fn fetch_full(&self, bucket: &str, key: &str) -> anyhow::Result<Bytes> {
+        let resp = self.client.get(Self::url(bucket, key)).send().await?.error_for_status()?;
+        Ok(resp.bytes().await?)
+    }
+
+    async fn fetch_range_stream(
+        &self,
+        bucket: &str,
+        key: &str,
+        start: usize,
+        end: usize,
+    ) -> anyhow::Result<Pin<Box<dyn Stream<Item = anyhow::Result<Bytes>> + Send>>> {
+        let resp = self
+            .client
+            .get(Self::url(bucket, key))
+            .header("Range", format_http_range(start, end))
+            .send()
+            .await?
+            .error_for_status()?;
+        let stream = resp.bytes_stream().map(|res| res.map_err(anyhow::Error::from));
+        Ok(Box::pin(stream))
+    }
+
+    async fn head(&self, bucket: &str, key: &str) -> anyhow::Result<ObjectMeta> {
+        let resp = self.client.head(Self::url(bucket, key)).send().await?.error_for_status()?;
+        let size = resp
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        let supports_ranges = resp
+            .headers()
+            .get(reqwest::header::ACCEPT_RANGES)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.eq_ignore_ascii_case("bytes"))
+            .unwrap_or(false);
+        Ok(ObjectMeta { size, supports_ranges })
+    }
+}
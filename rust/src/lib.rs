@@ -1,12 +1,92 @@
 use pyo3::prelude::*;
-use pyo3::types::{PyBytes, PyAsyncIterator};
 use pyo3_asyncio::tokio::future_into_py;
-use aws_sdk_s3::{Client, Region};
-use aws_config::meta::region::RegionProviderChain;
 use bytes::Bytes;
 use tokio_stream::{Stream, StreamExt};
+use tokio::io::{AsyncReadExt, BufReader};
+use tokio_util::io::StreamReader;
+use async_stream::try_stream;
+use async_compression::tokio::bufread::ZstdEncoder;
+use async_compression::Level;
+use futures::stream::FuturesUnordered;
 use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
 use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod errors;
+mod store;
+mod s3;
+mod gcs;
+mod azure;
+mod http;
+mod zip_extract;
+mod retry;
+
+use errors::RangeError;
+use retry::RetryPolicy;
+use store::ObjectStore;
+
+/// Size of the read buffer used to drain the compressor; unrelated to the S3 `range_size`.
+const COMPRESS_BUF_SIZE: usize = 64 * 1024;
+
+/// A caller-supplied byte range over the global logical stream (the concatenation of every
+/// `FileInfo`, in `file_start_offset` order), expressed the way HTTP `Range` headers do.
+enum ByteRangeSpec {
+    /// `bytes=start-end`
+    Bounded { start: usize, end: usize },
+    /// `bytes=start-` — from `start` to the end of the stream.
+    From { start: usize },
+    /// Not a real HTTP Range token (there's no `bytes=-end` — that notation means `Suffix`
+    /// below) — an internal form for "from the start of the stream to `end`".
+    To { end: usize },
+    /// `bytes=-length` — the last `length` bytes of the stream.
+    Suffix { length: usize },
+}
+
+/// Resolve a `ByteRangeSpec` against the total size of the logical stream, producing the
+/// `(start, end)` bounds `compute_file_ranges` expects, or a typed error if the range is
+/// inverted or reaches past the end of the data instead of silently truncating it.
+fn resolve_byte_range(
+    spec: Option<ByteRangeSpec>,
+    total_size: usize,
+) -> Result<(Option<usize>, Option<usize>), RangeError> {
+    let Some(spec) = spec else {
+        return Ok((None, None));
+    };
+
+    match spec {
+        ByteRangeSpec::Bounded { start, end } => {
+            if start > end {
+                return Err(RangeError::InvalidRange { start, end });
+            }
+            if end >= total_size {
+                return Err(RangeError::RangeOutOfBounds { value: end, file_length: total_size });
+            }
+            Ok((Some(start), Some(end)))
+        }
+        ByteRangeSpec::From { start } => {
+            if start >= total_size {
+                return Err(RangeError::RangeOutOfBounds { value: start, file_length: total_size });
+            }
+            Ok((Some(start), None))
+        }
+        ByteRangeSpec::To { end } => {
+            if end >= total_size {
+                return Err(RangeError::RangeOutOfBounds { value: end, file_length: total_size });
+            }
+            Ok((None, Some(end)))
+        }
+        ByteRangeSpec::Suffix { length } => {
+            if length == 0 {
+                return Err(RangeError::InvalidRange { start: 0, end: 0 });
+            }
+            // A suffix longer than the stream itself just means "the whole stream", per the
+            // usual HTTP `bytes=-N` convention, so this intentionally clamps rather than errors.
+            Ok((Some(total_size.saturating_sub(length)), None))
+        }
+    }
+}
 
 #[pyclass]
 #[derive(Clone)]
@@ -19,64 +99,71 @@ pub struct FileInfo {
     pub bucket_name: String,
     #[pyo3(get, set)]
     pub zip_filepath: Option<String>,
+    /// Offset of this file's first byte within the caller's global logical byte range.
+    #[pyo3(get, set)]
+    pub file_start_offset: usize,
+    /// Offset of the file's actual data within the underlying object (e.g. past a header).
+    #[pyo3(get, set)]
+    pub data_start_offset: usize,
+    /// Which `ObjectStore` backend serves this file: `"s3"`, `"gcs"`, `"azure"`, or `"http"`.
+    #[pyo3(get, set)]
+    pub provider: String,
+    /// Backend-specific connection detail: unused for S3, an optional host override for GCS,
+    /// the storage account (plus optional `?<sas-token>`) for Azure, and unused for HTTP
+    /// (where `bucket_name` already carries the scheme and host).
+    #[pyo3(get, set)]
+    pub endpoint: Option<String>,
 }
 
-// Shared Tokio runtime is auto-initialized by pyo3 with "auto-initialize" feature
-
-async fn fetch_range_streaming(
-    client: &Client,
-    bucket: &str,
-    key: &str,
-    start: usize,
-    end: usize,
-    sub_chunk_size: usize,
-) -> Result<impl Stream<Item = Result<Bytes>>> {
-    let range_header = format!("bytes={}-{}", start, end);
-    let resp = client
-        .get_object()
-        .bucket(bucket)
-        .key(key)
-        .range(range_header)
-        .send()
-        .await?;
-
-    let stream = resp.body;
-    let mut reader = StreamReader::new(stream.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)));
-
-    let stream = try_stream! {
-        let mut buf = vec![0u8; sub_chunk_size];
-        loop {
-            let n = reader.read(&mut buf).await?;
-            if n == 0 {
-                break;
-            }
-            yield Bytes::copy_from_slice(&buf[..n]);
-         }
+/// Build the `ObjectStore` backend for a given provider/endpoint pair (`resolve_files` caches
+/// the result per pair).
+async fn build_store(provider: &str, endpoint: Option<&str>, region: &str) -> Result<Arc<dyn ObjectStore>> {
+    let store: Arc<dyn ObjectStore> = match provider {
+        "s3" => Arc::new(s3::S3Store::new(region).await),
+        "gcs" => Arc::new(gcs::GcsStore::new(endpoint)),
+        "azure" => {
+            let endpoint = endpoint
+                .ok_or_else(|| anyhow::anyhow!("azure backend requires FileInfo.endpoint (storage account)"))?;
+            Arc::new(azure::AzureBlobStore::new(endpoint))
+        }
+        "http" | "https" => Arc::new(http::HttpStore::new()),
+        other => return Err(anyhow::anyhow!("unsupported object store provider: {other}")),
     };
+    Ok(store)
+}
 
-    Ok(stream)
+/// Whether a file's logical byte span (`[file_start_offset, file_start_offset + file_size)`)
+/// overlaps the caller-requested `byte_range` at all. Shared by every branch of `stream_files`
+/// so the Full and ZipMember fallback paths skip files lying wholly outside the requested range,
+/// as the chunked path does. Those fallback paths emit an overlapping file whole; only the
+/// chunked path clips it to the range. Each bound is
+/// checked independently: a missing bound (`None`) doesn't constrain that side at all, but a
+/// present one rules out files entirely before it (for `range_start`) or entirely after it (for
+/// `range_end`) — including the open-ended `From`/`To` forms, not just the fully-bounded one.
+fn file_in_byte_range(file_start_offset: usize, file_size: usize, byte_range: (Option<usize>, Option<usize>)) -> bool {
+    let file_end_exclusive = file_start_offset + file_size;
+    let (range_start, range_end) = byte_range;
+    let after_start = range_start.map_or(true, |range_start| file_end_exclusive > range_start);
+    let before_end = range_end.map_or(true, |range_end| file_start_offset <= range_end);
+    after_start && before_end
 }
 
 /// Compute byte ranges to download from a file, considering a global byte range and chunk size.
 /// `byte_range` = (Option<start>, Option<end>) in global logical stream coordinates.
 fn compute_file_ranges(
-    file_info: &FileInfo,
+    file: &ResolvedFile,
     byte_range: (Option<usize>, Option<usize>),
     range_size: usize,
 ) -> Option<Vec<(usize, usize)>> {
-    let file_size = file_info.size;
-    let file_start_offset = file_info.file_start_offset;
-    let file_end_offset = file_start_offset + file_size - 1;
-
-    let (range_start_opt, range_end_opt) = byte_range;
+    let file_size = file.size;
+    let file_start_offset = file.file_start_offset;
 
-    // Check for no overlap
-    if let (Some(range_start), Some(range_end)) = (range_start_opt, range_end_opt) {
-        if file_end_offset < range_start || file_start_offset > range_end {
-            return None; // no overlap, skip this file
-        }
+    if !file_in_byte_range(file_start_offset, file_size, byte_range) {
+        return None; // no overlap, skip this file
     }
 
+    let (range_start_opt, range_end_opt) = byte_range;
+
     // Calculate adjusted start/end inside the file
     let mut start = 0usize;
     let mut end = file_size - 1;
@@ -89,8 +176,8 @@ fn compute_file_ranges(
     }
 
     // Adjust for data offset inside file
-    start += file_info.data_start_offset;
-    end += file_info.data_start_offset;
+    start += file.data_start_offset;
+    end += file.data_start_offset;
 
     // Split into chunk ranges
     let mut ranges = Vec::new();
@@ -105,53 +192,303 @@ fn compute_file_ranges(
     Some(ranges)
 }
 
-/// Stream bytes chunk by chunk for multiple files
+/// What to actually request from the backend for one fetch task.
+enum FetchKind {
+    Range { start: usize, end: usize },
+    /// Emitted when a file's size couldn't be trusted or ranges aren't supported: fetch the
+    /// whole object in one shot instead of producing a (possibly malformed) `Range` header.
+    Full,
+    /// A single member inside a ZIP archive: fetch just its compressed data range, then
+    /// decompress it per the ZIP storage `method` before yielding to Python.
+    ZipMember { start: usize, end: usize, method: u16 },
+    /// A zero-length ZIP member (empty file or directory marker): nothing to fetch or
+    /// decompress, just yield an empty chunk.
+    EmptyZipMember,
+}
+
+/// One unit of work produced by flattening every file's chunk ranges: its position in the
+/// logical output order plus what to fetch to produce it.
+struct RangeTask {
+    seq_index: usize,
+    bucket: String,
+    key: String,
+    store: Arc<dyn ObjectStore>,
+    retry_policy: Arc<RetryPolicy>,
+    kind: FetchKind,
+}
+
+async fn fetch_task(task: RangeTask) -> (usize, Result<Bytes>) {
+    let store = task.store.as_ref();
+    let result = match task.kind {
+        FetchKind::Range { start, end } => {
+            retry::fetch_range_with_retry(store, &task.bucket, &task.key, start, end, &task.retry_policy).await
+        }
+        FetchKind::Full => retry::fetch_full_with_retry(store, &task.bucket, &task.key, &task.retry_policy).await,
+        FetchKind::ZipMember { start, end, method } => {
+            retry::fetch_range_with_retry(store, &task.bucket, &task.key, start, end, &task.retry_policy)
+                .await
+                .and_then(|data| zip_extract::decompress_member(data, method))
+        }
+        FetchKind::EmptyZipMember => Ok(Bytes::new()),
+    };
+    (task.seq_index, result)
+}
+
+/// A file's resolved download parameters, after filling in any size that the caller left at
+/// zero via the backend's `head` probe (the discovered size is written back onto the Python
+/// object as it's resolved, so callers can reuse it without re-probing).
+struct ResolvedFile {
+    bucket: String,
+    key: String,
+    size: usize,
+    file_start_offset: usize,
+    data_start_offset: usize,
+    zip_filepath: Option<String>,
+    supports_ranges: bool,
+    store: Arc<dyn ObjectStore>,
+}
+
+/// Snapshot every `FileInfo`, probing size/range-support for any file whose caller-supplied
+/// `size` is zero, and writing the discovered size back onto the Python object. Backends are
+/// built once per distinct `(provider, endpoint)` pair and shared across files.
+async fn resolve_files(region: &str, files: Vec<Py<FileInfo>>) -> Result<Vec<ResolvedFile>> {
+    let mut store_cache: HashMap<(String, Option<String>), Arc<dyn ObjectStore>> = HashMap::new();
+    let mut resolved = Vec::with_capacity(files.len());
+
+    for handle in files {
+        let (bucket, key, size, file_start_offset, data_start_offset, zip_filepath, provider, endpoint) =
+            Python::with_gil(|py| {
+                let file = handle.borrow(py);
+                (
+                    file.bucket_name.clone(),
+                    file.key.clone(),
+                    file.size,
+                    file.file_start_offset,
+                    file.data_start_offset,
+                    file.zip_filepath.clone(),
+                    file.provider.clone(),
+                    file.endpoint.clone(),
+                )
+            });
+
+        let cache_key = (provider.clone(), endpoint.clone());
+        let store = match store_cache.get(&cache_key) {
+            Some(store) => store.clone(),
+            None => {
+                let store = build_store(&provider, endpoint.as_deref(), region).await?;
+                store_cache.insert(cache_key, store.clone());
+                store
+            }
+        };
+
+        let (size, supports_ranges) = if size == 0 {
+            let meta = store.head(&bucket, &key).await?;
+            let size = meta.size.unwrap_or(0);
+            Python::with_gil(|py| {
+                handle.borrow_mut(py).size = size;
+            });
+            (size, meta.supports_ranges)
+        } else {
+            (size, true)
+        };
+
+        resolved.push(ResolvedFile {
+            bucket,
+            key,
+            size,
+            file_start_offset,
+            data_start_offset,
+            zip_filepath,
+            supports_ranges,
+            store,
+        });
+    }
+    Ok(resolved)
+}
+
+/// Stream bytes chunk by chunk for multiple files, fetching up to `max_concurrency` ranges in
+/// parallel while still emitting chunks in ascending logical order.
 async fn stream_files(
-    files: Vec<FileInfo>,
+    files: Vec<Py<FileInfo>>,
     range_size: usize,
     region: String,
+    max_concurrency: usize,
+    byte_range: Option<ByteRangeSpec>,
+    retry_policy: Arc<RetryPolicy>,
 ) -> Result<impl Stream<Item = Result<Bytes>>> {
-    // Configure AWS region (use default chain, fallback to provided region)
-    let region_provider = RegionProviderChain::default_provider().or_else(Region::new(region));
-    let config = aws_config::from_env().region(region_provider).load().await;
-    let client = Client::new(&config);
-
-    // Create a stream that yields chunks one by one for all files sequentially
-    let stream = tokio_stream::iter(files)
-        .flat_map(move |file| {
-            let client = client.clone();
-            let bucket = file.bucket_name.;
-            let key = file.key.;
-            let size = file.size;
-            let range_size = range_size;
-
-            // Stream over chunks for this file
-            let file_stream = async_stream::try_stream! {
-                let mut pos = 0usize;
-                while pos < size {
-                    let end = usize::min(pos + range_size - 1, size - 1);
-                    let chunk = fetch_range(&client, &bucket, &key, pos, end).await?;
-                    yield chunk;
-                    pos += range_size;
-                }
+    let files = resolve_files(&region, files).await?;
+
+    let total_size = files
+        .iter()
+        .map(|f| f.file_start_offset + f.size)
+        .max()
+        .unwrap_or(0);
+    let byte_range = resolve_byte_range(byte_range, total_size)?;
+
+    // Flatten every file's chunk ranges into a single sequence of fetch tasks, numbered so the
+    // reassembly stage below can emit them back in order regardless of completion order.
+    let mut tasks = Vec::new();
+    for file in &files {
+        if !file_in_byte_range(file.file_start_offset, file.size, byte_range) {
+            continue;
+        }
+
+        if let Some(member_name) = &file.zip_filepath {
+            let location = zip_extract::locate_member(
+                file.store.as_ref(),
+                &file.bucket,
+                &file.key,
+                file.size,
+                member_name,
+                &retry_policy,
+            )
+            .await?;
+            let kind = match location.range {
+                Some((start, end)) => FetchKind::ZipMember { start, end, method: location.method },
+                None => FetchKind::EmptyZipMember,
             };
-            file_stream
-        });
+            let seq_index = tasks.len();
+            tasks.push(RangeTask {
+                seq_index,
+                bucket: file.bucket.clone(),
+                key: file.key.clone(),
+                store: file.store.clone(),
+                retry_policy: retry_policy.clone(),
+                kind,
+            });
+            continue;
+        }
+
+        if file.size == 0 || !file.supports_ranges {
+            // Unknown length or no range support: a single `Range` request would either be
+            // malformed or silently ignored, so fetch the whole object instead.
+            let seq_index = tasks.len();
+            tasks.push(RangeTask {
+                seq_index,
+                bucket: file.bucket.clone(),
+                key: file.key.clone(),
+                store: file.store.clone(),
+                retry_policy: retry_policy.clone(),
+                kind: FetchKind::Full,
+            });
+            continue;
+        }
+
+        let Some(ranges) = compute_file_ranges(file, byte_range, range_size) else {
+            continue;
+        };
+        for (start, end) in ranges {
+            let seq_index = tasks.len();
+            tasks.push(RangeTask {
+                seq_index,
+                bucket: file.bucket.clone(),
+                key: file.key.clone(),
+                store: file.store.clone(),
+                retry_policy: retry_policy.clone(),
+                kind: FetchKind::Range { start, end },
+            });
+        }
+    }
+
+    let max_concurrency = max_concurrency.max(1);
+
+    let stream = try_stream! {
+        let mut pending = tasks.into_iter();
+        let mut in_flight = FuturesUnordered::new();
+        let mut reorder_buffer: BTreeMap<usize, Bytes> = BTreeMap::new();
+        let mut next_to_emit = 0usize;
+
+        for task in pending.by_ref().take(max_concurrency) {
+            in_flight.push(fetch_task(task));
+        }
+
+        while let Some((seq_index, result)) = in_flight.next().await {
+            reorder_buffer.insert(seq_index, result?);
+
+            if let Some(task) = pending.next() {
+                in_flight.push(fetch_task(task));
+            }
+
+            while let Some(chunk) = reorder_buffer.remove(&next_to_emit) {
+                yield chunk;
+                next_to_emit += 1;
+            }
+        }
+    };
+
     Ok(stream)
 }
 
+/// Wrap a chunk stream in a streaming zstd encoder, so callers receive compressed frames
+/// instead of raw bytes. Reading the encoder through to EOF flushes the final frame.
+fn compress_zstd(
+    stream: impl Stream<Item = Result<Bytes>> + Send + 'static,
+    level: i32,
+) -> impl Stream<Item = Result<Bytes>> {
+    let reader = StreamReader::new(stream.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)));
+    let mut encoder = ZstdEncoder::with_quality(BufReader::new(reader), Level::Precise(level));
+
+    try_stream! {
+        let mut buf = vec![0u8; COMPRESS_BUF_SIZE];
+        loop {
+            let n = encoder.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            yield Bytes::copy_from_slice(&buf[..n]);
+        }
+    }
+}
+
 #[pyfunction]
 fn stream_download_from_s3_py(
     py: Python,
-    files: Vec<FileInfo>,
+    files: Vec<Py<FileInfo>>,
     range_size: usize,
     region: String,
+    max_concurrency: usize,
+    compress: Option<String>,
+    compress_level: Option<i32>,
+    range_start: Option<usize>,
+    range_end: Option<usize>,
+    suffix_length: Option<usize>,
+    max_retries: Option<u32>,
+    base_delay_ms: Option<u64>,
 ) -> PyResult<PyObject> {
+    let retry_policy = Arc::new(RetryPolicy {
+        max_retries: max_retries.unwrap_or(3),
+        base_delay: Duration::from_millis(base_delay_ms.unwrap_or(200)),
+    });
+    // At most one of the four range forms may be given: start and end (`bytes=start-end`),
+    // start only (`bytes=start-`), end only (`ByteRangeSpec::To`), or suffix_length only
+    // (`bytes=-length`). Any other combination is rejected.
+    let byte_range = match (range_start, range_end, suffix_length) {
+        (None, None, None) => None,
+        (Some(start), Some(end), None) => Some(ByteRangeSpec::Bounded { start, end }),
+        (Some(start), None, None) => Some(ByteRangeSpec::From { start }),
+        (None, Some(end), None) => Some(ByteRangeSpec::To { end }),
+        (None, None, Some(length)) => Some(ByteRangeSpec::Suffix { length }),
+        _ => {
+            return Err(RangeError::InvalidRange {
+                start: range_start.unwrap_or(0),
+                end: range_end.unwrap_or(0),
+            }
+            .into())
+        }
+    };
+
     // Convert Rust Stream into Python async generator using pyo3-asyncio
     future_into_py(py, async move {
-        let stream = stream_files(files, range_size, region).await?;
+        let stream = stream_files(files, range_size, region, max_concurrency, byte_range, retry_policy).await?;
+
+        let compressed: Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>> = match compress.as_deref() {
+            Some("zstd") => Box::pin(compress_zstd(stream, compress_level.unwrap_or(0))),
+            Some(other) => return Err(anyhow::anyhow!("unsupported compression codec: {other}").into()),
+            None => Box::pin(stream),
+        };
+
         // Convert each bytes chunk to Python bytes
-        let py_stream = stream.map(|res| res.map(|chunk| chunk.to_vec()));
+        let py_stream = compressed.map(|res| res.map(|chunk| chunk.to_vec()));
         Ok::<_, anyhow::Error>(py_stream)
     })
 }
@@ -162,3 +499,155 @@ fn s3_streamer(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(stream_download_from_s3_py, m)?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use store::ObjectMeta;
+
+    /// Never actually called by these tests; `compute_file_ranges` is pure arithmetic over
+    /// `ResolvedFile`'s fields and never touches its `store`.
+    struct NullStore;
+
+    #[async_trait]
+    impl ObjectStore for NullStore {
+        async fn fetch_range(&self, _bucket: &str, _key: &str, _start: usize, _end: usize) -> Result<Bytes> {
+            unimplemented!()
+        }
+        async fn fetch_full(&self, _bucket: &str, _key: &str) -> Result<Bytes> {
+            unimplemented!()
+        }
+        async fn fetch_range_stream(
+            &self,
+            _bucket: &str,
+            _key: &str,
+            _start: usize,
+            _end: usize,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>> {
+            unimplemented!()
+        }
+        async fn head(&self, _bucket: &str, _key: &str) -> Result<ObjectMeta> {
+            unimplemented!()
+        }
+    }
+
+    fn resolved_file(size: usize, file_start_offset: usize, data_start_offset: usize) -> ResolvedFile {
+        ResolvedFile {
+            bucket: "bucket".to_string(),
+            key: "key".to_string(),
+            size,
+            file_start_offset,
+            data_start_offset,
+            zip_filepath: None,
+            supports_ranges: true,
+            store: Arc::new(NullStore),
+        }
+    }
+
+    #[test]
+    fn resolve_byte_range_passes_through_when_unset() {
+        assert_eq!(resolve_byte_range(None, 100).unwrap(), (None, None));
+    }
+
+    #[test]
+    fn resolve_byte_range_bounded() {
+        let spec = ByteRangeSpec::Bounded { start: 10, end: 20 };
+        assert_eq!(resolve_byte_range(Some(spec), 100).unwrap(), (Some(10), Some(20)));
+    }
+
+    #[test]
+    fn resolve_byte_range_bounded_rejects_inverted_range() {
+        let spec = ByteRangeSpec::Bounded { start: 20, end: 10 };
+        assert!(resolve_byte_range(Some(spec), 100).is_err());
+    }
+
+    #[test]
+    fn resolve_byte_range_bounded_rejects_end_past_total_size() {
+        let spec = ByteRangeSpec::Bounded { start: 0, end: 100 };
+        assert!(resolve_byte_range(Some(spec), 100).is_err());
+    }
+
+    #[test]
+    fn resolve_byte_range_from_is_open_ended() {
+        let spec = ByteRangeSpec::From { start: 10 };
+        assert_eq!(resolve_byte_range(Some(spec), 100).unwrap(), (Some(10), None));
+    }
+
+    #[test]
+    fn resolve_byte_range_from_rejects_start_past_total_size() {
+        let spec = ByteRangeSpec::From { start: 100 };
+        assert!(resolve_byte_range(Some(spec), 100).is_err());
+    }
+
+    #[test]
+    fn resolve_byte_range_to_is_open_started() {
+        let spec = ByteRangeSpec::To { end: 10 };
+        assert_eq!(resolve_byte_range(Some(spec), 100).unwrap(), (None, Some(10)));
+    }
+
+    #[test]
+    fn resolve_byte_range_suffix() {
+        let spec = ByteRangeSpec::Suffix { length: 10 };
+        assert_eq!(resolve_byte_range(Some(spec), 100).unwrap(), (Some(90), None));
+    }
+
+    #[test]
+    fn resolve_byte_range_suffix_longer_than_total_clamps_to_whole_stream() {
+        let spec = ByteRangeSpec::Suffix { length: 1000 };
+        assert_eq!(resolve_byte_range(Some(spec), 100).unwrap(), (Some(0), None));
+    }
+
+    #[test]
+    fn resolve_byte_range_suffix_rejects_zero_length() {
+        let spec = ByteRangeSpec::Suffix { length: 0 };
+        assert!(resolve_byte_range(Some(spec), 100).is_err());
+    }
+
+    #[test]
+    fn file_in_byte_range_excludes_file_entirely_before_an_open_ended_range() {
+        // Three files at global offsets [0,1000), [1000,2000), [2000,3000); caller asked for a
+        // `suffix_length=100` (resolved to `(Some(2900), None)`). File 0 is nowhere near it, and
+        // this is the only gate the Full/ZipMember fallback paths in stream_files apply — unlike
+        // compute_file_ranges, they never clip afterward — so this must actually exclude it.
+        assert!(!file_in_byte_range(0, 1000, (Some(2900), None)));
+        assert!(!file_in_byte_range(1000, 1000, (Some(2900), None)));
+        assert!(file_in_byte_range(2000, 1000, (Some(2900), None)));
+    }
+
+    #[test]
+    fn file_in_byte_range_excludes_file_entirely_after_an_open_started_range() {
+        // `(None, Some(end))` form (ByteRangeSpec::To): only files starting at or before `end`
+        // overlap.
+        assert!(file_in_byte_range(0, 1000, (None, Some(500))));
+        assert!(!file_in_byte_range(1000, 1000, (None, Some(500))));
+    }
+
+    #[test]
+    fn compute_file_ranges_splits_into_chunks() {
+        let file = resolved_file(10, 0, 0);
+        let ranges = compute_file_ranges(&file, (None, None), 4).unwrap();
+        assert_eq!(ranges, vec![(0, 3), (4, 7), (8, 9)]);
+    }
+
+    #[test]
+    fn compute_file_ranges_returns_none_when_outside_requested_range() {
+        let file = resolved_file(10, 100, 0);
+        assert!(compute_file_ranges(&file, (Some(0), Some(50)), 4).is_none());
+    }
+
+    #[test]
+    fn compute_file_ranges_clips_to_overlapping_portion_of_requested_range() {
+        // File occupies global offsets [10, 20). Caller asked for global bytes [15, 100].
+        let file = resolved_file(10, 10, 0);
+        let ranges = compute_file_ranges(&file, (Some(15), Some(100)), 100).unwrap();
+        assert_eq!(ranges, vec![(5, 9)]);
+    }
+
+    #[test]
+    fn compute_file_ranges_honors_data_start_offset() {
+        let file = resolved_file(10, 0, 100);
+        let ranges = compute_file_ranges(&file, (None, None), 100).unwrap();
+        assert_eq!(ranges, vec![(100, 109)]);
+    }
+}
@@ -0,0 +1,156 @@
+//! Retry-with-backoff for transient fetch failures, with partial-range resume for the
+//! streaming path so a retried request doesn't re-download bytes it already received.
+
+use crate::store::ObjectStore;
+use anyhow::Result;
+use bytes::{Bytes, BytesMut};
+use rand::Rng;
+use std::time::Duration;
+use tokio_stream::StreamExt;
+
+/// How hard to retry a transient failure before giving up and propagating the error.
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+/// Best-effort classification of which errors are worth retrying: timeouts, connection drops,
+/// and server-side/throttling responses. Everything else (bad credentials, 404s, ...) is
+/// treated as permanent, since retrying it would just waste time before failing the same way.
+fn is_retryable(err: &anyhow::Error) -> bool {
+    let message = err.to_string().to_lowercase();
+    ["timeout", "timed out", "connection reset", "connection closed", "broken pipe", "throttl", "500", "502", "503"]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+/// Exponential backoff with full jitter: `base_delay * 2^attempt`, scaled by a random factor in
+/// `[0, 1)` so that many concurrent retries don't all wake up and retry in lockstep.
+fn backoff_delay(base_delay: Duration, attempt: u32) -> Duration {
+    let capped_attempt = attempt.min(20);
+    let max_delay = base_delay.saturating_mul(1u32 << capped_attempt);
+    max_delay.mul_f64(rand::thread_rng().gen::<f64>())
+}
+
+/// Fetch the whole object with retry-with-backoff. There's no known range to resume from here
+/// (a full fetch is used when the size is unknown or ranged reads aren't supported), so a retry
+/// restarts from zero.
+pub async fn fetch_full_with_retry(
+    store: &dyn ObjectStore,
+    bucket: &str,
+    key: &str,
+    policy: &RetryPolicy,
+) -> Result<Bytes> {
+    let mut attempt = 0u32;
+    loop {
+        match store.fetch_full(bucket, key).await {
+            Ok(bytes) => return Ok(bytes),
+            Err(err) if attempt < policy.max_retries && is_retryable(&err) => {
+                attempt += 1;
+                tokio::time::sleep(backoff_delay(policy.base_delay, attempt)).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Fetch the inclusive byte range `[start, end]` with retry-with-backoff. On a transient error
+/// partway through the stream, the next attempt only re-requests `bytes={start+received}-{end}`
+/// instead of re-downloading bytes this caller already has.
+pub async fn fetch_range_with_retry(
+    store: &dyn ObjectStore,
+    bucket: &str,
+    key: &str,
+    start: usize,
+    end: usize,
+    policy: &RetryPolicy,
+) -> Result<Bytes> {
+    let expected_len = end - start + 1;
+    let mut received = BytesMut::new();
+    let mut attempt = 0u32;
+
+    loop {
+        let resume_start = start + received.len();
+        let mut stream = match store.fetch_range_stream(bucket, key, resume_start, end).await {
+            Ok(stream) => stream,
+            Err(err) if attempt < policy.max_retries && is_retryable(&err) => {
+                attempt += 1;
+                tokio::time::sleep(backoff_delay(policy.base_delay, attempt)).await;
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+
+        let mut retry_after_partial_failure = false;
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(bytes) => received.extend_from_slice(&bytes),
+                Err(err) if attempt < policy.max_retries && is_retryable(&err) => {
+                    attempt += 1;
+                    retry_after_partial_failure = true;
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        if retry_after_partial_failure {
+            tokio::time::sleep(backoff_delay(policy.base_delay, attempt)).await;
+            continue;
+        }
+
+        // The stream ended without an explicit error, but that doesn't guarantee the backend
+        // actually sent the whole range (e.g. a connection dropped in a way the SDK/reqwest
+        // surfaces as a clean EOF rather than an error). Treat a short read the same as any
+        // other retryable failure instead of silently handing back a truncated chunk.
+        if received.len() < expected_len {
+            if attempt < policy.max_retries {
+                attempt += 1;
+                tokio::time::sleep(backoff_delay(policy.base_delay, attempt)).await;
+                continue;
+            }
+            return Err(anyhow::anyhow!(
+                "short read fetching bytes {start}-{end} of {key}: got {} of {expected_len} bytes",
+                received.len()
+            ));
+        }
+
+        return Ok(received.freeze());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_retryable_matches_known_transient_errors() {
+        for message in ["request timed out", "connection reset by peer", "503 Service Unavailable", "throttled"] {
+            assert!(is_retryable(&anyhow::anyhow!("{message}")), "expected {message:?} to be retryable");
+        }
+    }
+
+    #[test]
+    fn is_retryable_rejects_permanent_errors() {
+        for message in ["404 Not Found", "InvalidAccessKeyId", "access denied"] {
+            assert!(!is_retryable(&anyhow::anyhow!("{message}")), "expected {message:?} to be permanent");
+        }
+    }
+
+    #[test]
+    fn backoff_delay_grows_with_attempt_and_stays_within_the_jitter_bound() {
+        let base = Duration::from_millis(100);
+        for attempt in 1..=5 {
+            let delay = backoff_delay(base, attempt);
+            let max = base.saturating_mul(1u32 << attempt);
+            assert!(delay <= max, "attempt {attempt}: {delay:?} exceeded max {max:?}");
+        }
+    }
+
+    #[test]
+    fn backoff_delay_caps_the_exponent_to_avoid_overflow() {
+        // Without the cap, `1u32 << attempt` would panic for attempt >= 32.
+        let delay = backoff_delay(Duration::from_millis(100), 1000);
+        assert!(delay <= Duration::from_millis(100).saturating_mul(1u32 << 20));
+    }
+}
@@ -1,78 +1,71 @@
-use pyo3::prelude::*;
+use crate::store::{format_http_range, ObjectMeta, ObjectStore};
+use async_trait::async_trait;
+use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_s3::{Client, Region};
-use aws_sdk_s3::primitives::ByteStream;
-use tokio::runtime::Runtime;
-use std::cmp::min;
+use bytes::Bytes;
+use std::pin::Pin;
+use tokio_stream::{Stream, StreamExt};
 
-#[pyclass]
-#[derive(Clone)]
-pub struct FileInfo {
-    #[pyo3(get, set)]
-    pub size: usize,
-    #[pyo3(get, set)]
-    pub key: String,
-    #[pyo3(get, set)]
-    pub bucket_name: String,
-    #[pyo3(get, set)]
-    pub zip_filepath: Option<String>,
+/// `ObjectStore` backed by Amazon S3 (or an S3-compatible endpoint).
+pub struct S3Store {
+    client: Client,
 }
 
-#[pyfunction]
-pub fn stream_download_from_s3_py(
-    py: Python,
-    files: Vec<FileInfo>,
-    range_size: usize,
-    region: String,
-) -> PyResult<PyObject> {
-    let rt = Runtime::new().unwrap();
-    let output_stream = py.allow_threads(move || {
-        rt.block_on(stream_files(files, range_size, region))
-    })?;
-
-    // Convert Vec<Vec<u8>> to Python list of bytes
-    let py_list = PyList::new(py, output_stream.into_iter().map(|chunk| PyBytes::new(py, &chunk)));
-    Ok(py_list.into_py(py))
+impl S3Store {
+    pub async fn new(region: &str) -> Self {
+        let region_provider = RegionProviderChain::default_provider().or_else(Region::new(region.to_string()));
+        let config = aws_config::from_env().region(region_provider).load().await;
+        Self { client: Client::new(&config) }
+    }
 }
 
-async fn fetch_range(
-    client: &Client,
-    bucket: &str,
-    key: &str,
-    start: usize,
-    end: usize,
-) -> anyhow::Result<bytes::Bytes> {
-    let range_header = format!("bytes={}-{}", start, end);
-    let resp = client
-        .get_object()
-        .bucket(bucket)
-        .key(key)
-        .range(range_header)
-        .send()
-        .await?;
+#[async_trait]
+impl ObjectStore for S3Store {
+    async fn fetch_range(&self, bucket: &str, key: &str, start: usize, end: usize) -> anyhow::Result<Bytes> {
+        let resp = self
+            .client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .range(format_http_range(start, end))
+            .send()
+            .await?;
 
-    let data = resp.body.collect().await?.into_bytes();
-    Ok(data)
-}
+        Ok(resp.body.collect().await?.into_bytes())
+    }
 
-async fn stream_files(
-    files: Vec<FileInfo>,
-    range_size: usize,
-    region: String,
-) -> anyhow::Result<Vec<Vec<u8>>> {
-    let config = aws_config::from_env().region(Region::new(region)).load().await;
-    let client = Client::new(&config);
+    async fn fetch_full(&self, bucket: &str, key: &str) -> anyhow::Result<Bytes> {
+        let resp = self.client.get_object().bucket(bucket).key(key).send().await?;
+        Ok(resp.body.collect().await?.into_bytes())
+    }
+
+    async fn fetch_range_stream(
+        &self,
+        bucket: &str,
+        key: &str,
+        start: usize,
+        end: usize,
+    ) -> anyhow::Result<Pin<Box<dyn Stream<Item = anyhow::Result<Bytes>> + Send>>> {
+        let resp = self
+            .client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .range(format_http_range(start, end))
+            .send()
+            .await?;
 
-    let mut output = vec![];
-    for file in files.iter() {
-        let size = file.size;
-        let mut pos = 0;
-        while pos < size {
-            let end = min(pos + range_size - 1, size - 1);
-            let chunk = fetch_range(&client, &file.bucket_name, &file.key, pos, end).await?;
-            output.push(chunk.to_vec());
-            pos += range_size;
-        }
+        let stream = resp.body.map(|res| res.map_err(anyhow::Error::from));
+        Ok(Box::pin(stream))
     }
 
-    Ok(output)
+    async fn head(&self, bucket: &str, key: &str) -> anyhow::Result<ObjectMeta> {
+        let resp = self.client.head_object().bucket(bucket).key(key).send().await?;
+        let size = resp.content_length().and_then(|len| usize::try_from(len).ok());
+        let supports_ranges = resp
+            .accept_ranges()
+            .map(|v| v.eq_ignore_ascii_case("bytes"))
+            .unwrap_or(false);
+        Ok(ObjectMeta { size, supports_ranges })
+    }
 }
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::pin::Pin;
+use tokio_stream::Stream;
+
+/// Metadata discovered about a remote object, as returned by `ObjectStore::head`.
+pub struct ObjectMeta {
+    /// `None` when the backend couldn't report a length at all (e.g. a chunked HTTP response).
+    pub size: Option<usize>,
+    pub supports_ranges: bool,
+}
+
+/// A backend capable of serving ranged reads against one provider's object storage API.
+/// `stream_files` is generic over this trait so the concurrent-fetch, reassembly and retry
+/// machinery only has to be written once, regardless of which catalog a product's files live in.
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Fetch the inclusive byte range `[start, end]` of `bucket/key`.
+    async fn fetch_range(&self, bucket: &str, key: &str, start: usize, end: usize) -> Result<Bytes>;
+
+    /// Fetch the whole object, for backends/objects that don't support ranged reads.
+    async fn fetch_full(&self, bucket: &str, key: &str) -> Result<Bytes>;
+
+    /// Like `fetch_range`, but as an incremental byte stream rather than one collected buffer,
+    /// so a caller that loses the connection partway through can see exactly how many bytes it
+    /// already received and retry only the remainder.
+    async fn fetch_range_stream(
+        &self,
+        bucket: &str,
+        key: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>>;
+
+    /// Probe an object's size and range support without downloading it.
+    async fn head(&self, bucket: &str, key: &str) -> Result<ObjectMeta>;
+}
+
+/// Format an HTTP `Range: bytes=start-end` header value. Every current backend (S3, GCS,
+/// Azure Blob, and the generic HTTP backend) uses this same value format (Azure Blob sends it
+/// as `x-ms-range`, the others as `Range`), so it's centralized here instead of being
+/// re-implemented per backend.
+pub fn format_http_range(start: usize, end: usize) -> String {
+    format!("bytes={start}-{end}")
+}
@@ -0,0 +1,440 @@
+//! On-the-fly extraction of a single member from a remote ZIP archive, using only the handful
+//! of range reads needed to walk its central directory — never downloading the whole archive.
+
+use crate::retry::{fetch_range_with_retry, RetryPolicy};
+use crate::store::ObjectStore;
+use anyhow::{anyhow, Result};
+use bytes::Bytes;
+use std::io::Read;
+
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const CENTRAL_DIR_SIGNATURE: u32 = 0x0201_4b50;
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const EOCD_FIXED_SIZE: usize = 22;
+/// A ZIP comment can be at most u16::MAX bytes; add that to the fixed EOCD size to bound how
+/// far back from the end of the archive we need to search for the record.
+const MAX_COMMENT_LEN: usize = u16::MAX as usize;
+
+/// Where a member's compressed bytes live in the archive, and how they're stored. `range` is
+/// `None` for a zero-length member (empty files and directory markers are legitimately stored
+/// with `compressed_size == 0`), since there's no byte to fetch and no range header that could
+/// express it.
+pub struct ZipMemberLocation {
+    pub range: Option<(usize, usize)>,
+    pub method: u16,
+}
+
+struct Eocd {
+    cd_offset: usize,
+    cd_size: usize,
+}
+
+struct CentralDirEntry {
+    method: u16,
+    compressed_size: usize,
+    local_header_offset: usize,
+}
+
+/// Bounds-checked little-endian reads, so a truncated or malformed central directory/header
+/// (corrupted archive, or lengths that don't line up with the bytes actually fetched) surfaces
+/// as an `Err` — and ultimately a Python exception — instead of panicking the whole extension.
+fn read_u16(buf: &[u8], offset: usize) -> Result<u16> {
+    let bytes = buf.get(offset..offset + 2).ok_or_else(|| {
+        anyhow!("truncated zip data: cannot read u16 at offset {offset} (buffer is {} bytes)", buf.len())
+    })?;
+    Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Result<u32> {
+    let bytes = buf.get(offset..offset + 4).ok_or_else(|| {
+        anyhow!("truncated zip data: cannot read u32 at offset {offset} (buffer is {} bytes)", buf.len())
+    })?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Range-fetch the tail of the archive and search backwards for the End of Central Directory
+/// record, which tells us where the central directory itself lives.
+async fn read_eocd(
+    store: &dyn ObjectStore,
+    bucket: &str,
+    key: &str,
+    archive_size: usize,
+    retry_policy: &RetryPolicy,
+) -> Result<Eocd> {
+    let search_len = (EOCD_FIXED_SIZE + MAX_COMMENT_LEN).min(archive_size);
+    let tail_start = archive_size - search_len;
+    let tail = fetch_range_with_retry(store, bucket, key, tail_start, archive_size - 1, retry_policy).await?;
+
+    let signature = EOCD_SIGNATURE.to_le_bytes();
+    let found = tail
+        .windows(4)
+        .rposition(|window| window == signature)
+        .ok_or_else(|| anyhow!("end of central directory record not found in {key}"))?;
+
+    let eocd = &tail[found..];
+    if eocd.len() < EOCD_FIXED_SIZE {
+        return Err(anyhow!("truncated end of central directory record in {key}"));
+    }
+
+    Ok(Eocd {
+        cd_size: read_u32(eocd, 12)? as usize,
+        cd_offset: read_u32(eocd, 16)? as usize,
+    })
+}
+
+/// Range-fetch the central directory and scan its entries for one matching `member_name`.
+async fn find_central_directory_entry(
+    store: &dyn ObjectStore,
+    bucket: &str,
+    key: &str,
+    eocd: &Eocd,
+    member_name: &str,
+    retry_policy: &RetryPolicy,
+) -> Result<CentralDirEntry> {
+    let cd =
+        fetch_range_with_retry(store, bucket, key, eocd.cd_offset, eocd.cd_offset + eocd.cd_size - 1, retry_policy)
+            .await?;
+
+    const ENTRY_FIXED_SIZE: usize = 46;
+    let mut pos = 0usize;
+    while pos + ENTRY_FIXED_SIZE <= cd.len() {
+        if read_u32(&cd, pos)? != CENTRAL_DIR_SIGNATURE {
+            break;
+        }
+
+        let method = read_u16(&cd, pos + 10)?;
+        let compressed_size = read_u32(&cd, pos + 20)? as usize;
+        let name_len = read_u16(&cd, pos + 28)? as usize;
+        let extra_len = read_u16(&cd, pos + 30)? as usize;
+        let comment_len = read_u16(&cd, pos + 32)? as usize;
+        let local_header_offset = read_u32(&cd, pos + 42)? as usize;
+
+        let name_start = pos + ENTRY_FIXED_SIZE;
+        let name_end = name_start + name_len;
+        let name_bytes = cd.get(name_start..name_end).ok_or_else(|| {
+            anyhow!("truncated zip central directory in {key}: entry name extends past fetched bytes")
+        })?;
+        let name = std::str::from_utf8(name_bytes).unwrap_or_default();
+
+        if name == member_name {
+            return Ok(CentralDirEntry { method, compressed_size, local_header_offset });
+        }
+
+        pos = name_end + extra_len + comment_len;
+    }
+
+    Err(anyhow!("member {member_name} not found in zip central directory of {key}"))
+}
+
+/// Read the member's local file header to compute the exact byte range of its compressed data
+/// (the filename/extra-field lengths that sit in front of the data are the local header's own,
+/// and those can differ from the central directory's copies).
+async fn resolve_data_range(
+    store: &dyn ObjectStore,
+    bucket: &str,
+    key: &str,
+    entry: &CentralDirEntry,
+    retry_policy: &RetryPolicy,
+) -> Result<Option<(usize, usize)>> {
+    if entry.compressed_size == 0 {
+        // Nothing to range-fetch for an empty file or directory marker; resolving the header
+        // would just tell us where zero bytes start.
+        return Ok(None);
+    }
+
+    const LOCAL_HEADER_FIXED_SIZE: usize = 30;
+    let header = fetch_range_with_retry(
+        store,
+        bucket,
+        key,
+        entry.local_header_offset,
+        entry.local_header_offset + LOCAL_HEADER_FIXED_SIZE - 1,
+        retry_policy,
+    )
+    .await?;
+
+    if read_u32(&header, 0)? != LOCAL_FILE_HEADER_SIGNATURE {
+        return Err(anyhow!("local file header signature mismatch at offset {}", entry.local_header_offset));
+    }
+
+    let name_len = read_u16(&header, 26)? as usize;
+    let extra_len = read_u16(&header, 28)? as usize;
+
+    let data_start = entry.local_header_offset + LOCAL_HEADER_FIXED_SIZE + name_len + extra_len;
+    let data_end = data_start + entry.compressed_size - 1;
+    Ok(Some((data_start, data_end)))
+}
+
+/// Locate `member_name` inside the ZIP archive at `bucket/key`, returning the exact byte range
+/// of its compressed data and its storage method, without downloading the archive.
+pub async fn locate_member(
+    store: &dyn ObjectStore,
+    bucket: &str,
+    key: &str,
+    archive_size: usize,
+    member_name: &str,
+    retry_policy: &RetryPolicy,
+) -> Result<ZipMemberLocation> {
+    let eocd = read_eocd(store, bucket, key, archive_size, retry_policy).await?;
+    let entry = find_central_directory_entry(store, bucket, key, &eocd, member_name, retry_policy).await?;
+    let range = resolve_data_range(store, bucket, key, &entry, retry_policy).await?;
+    Ok(ZipMemberLocation { range, method: entry.method })
+}
+
+/// Decompress one member's already-fetched compressed bytes per its ZIP storage method.
+/// Method `0` is stored (no compression); method `8` is deflate, the overwhelming majority
+/// case for EO product archives.
+pub fn decompress_member(data: Bytes, method: u16) -> Result<Bytes> {
+    match method {
+        0 => Ok(data),
+        8 => {
+            let mut decoder = flate2::read::DeflateDecoder::new(std::io::Cursor::new(data));
+            let mut out = Vec::new();
+            decoder.read_to_end(&mut out)?;
+            Ok(Bytes::from(out))
+        }
+        other => Err(anyhow!("unsupported zip compression method {other}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::ObjectMeta;
+    use async_trait::async_trait;
+    use std::pin::Pin;
+    use std::time::Duration;
+    use tokio_stream::Stream;
+
+    /// Serves range reads straight out of an in-memory buffer, so the central-directory walk
+    /// can be exercised against a hand-built archive without any network access.
+    struct InMemoryStore {
+        data: Vec<u8>,
+    }
+
+    #[async_trait]
+    impl ObjectStore for InMemoryStore {
+        async fn fetch_range(&self, _bucket: &str, _key: &str, start: usize, end: usize) -> Result<Bytes> {
+            Ok(Bytes::copy_from_slice(&self.data[start..=end]))
+        }
+
+        async fn fetch_full(&self, _bucket: &str, _key: &str) -> Result<Bytes> {
+            Ok(Bytes::copy_from_slice(&self.data))
+        }
+
+        async fn fetch_range_stream(
+            &self,
+            _bucket: &str,
+            _key: &str,
+            start: usize,
+            end: usize,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>> {
+            // `locate_member` reads through `fetch_range_with_retry`, which uses this method.
+            let chunk = Bytes::copy_from_slice(&self.data[start..=end]);
+            Ok(Box::pin(tokio_stream::once(Ok::<Bytes, anyhow::Error>(chunk))))
+        }
+
+        async fn head(&self, _bucket: &str, _key: &str) -> Result<ObjectMeta> {
+            unimplemented!("not exercised by the zip_extract tests")
+        }
+    }
+
+    fn push_u16(buf: &mut Vec<u8>, v: u16) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn push_u32(buf: &mut Vec<u8>, v: u32) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    /// Build a minimal ZIP archive by hand, containing one stored member with data and one
+    /// zero-length (empty file / directory marker) member, so `locate_member` can be exercised
+    /// end to end without an external fixture file.
+    fn build_test_zip() -> Vec<u8> {
+        let mut buf = Vec::new();
+
+        let a_name = b"a.txt";
+        let a_data = b"hi";
+        let a_local_offset = buf.len();
+        push_u32(&mut buf, LOCAL_FILE_HEADER_SIGNATURE);
+        push_u16(&mut buf, 20); // version needed
+        push_u16(&mut buf, 0); // flags
+        push_u16(&mut buf, 0); // method: stored
+        push_u16(&mut buf, 0); // mod time
+        push_u16(&mut buf, 0); // mod date
+        push_u32(&mut buf, 0); // crc32 (unchecked by decompress_member)
+        push_u32(&mut buf, a_data.len() as u32); // compressed size
+        push_u32(&mut buf, a_data.len() as u32); // uncompressed size
+        push_u16(&mut buf, a_name.len() as u16);
+        push_u16(&mut buf, 0); // extra len
+        buf.extend_from_slice(a_name);
+        buf.extend_from_slice(a_data);
+
+        let empty_name = b"empty.bin";
+        let empty_local_offset = buf.len();
+        push_u32(&mut buf, LOCAL_FILE_HEADER_SIGNATURE);
+        push_u16(&mut buf, 20);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u32(&mut buf, 0);
+        push_u32(&mut buf, 0); // compressed size
+        push_u32(&mut buf, 0); // uncompressed size
+        push_u16(&mut buf, empty_name.len() as u16);
+        push_u16(&mut buf, 0);
+        buf.extend_from_slice(empty_name);
+
+        let cd_offset = buf.len();
+
+        push_u32(&mut buf, CENTRAL_DIR_SIGNATURE);
+        push_u16(&mut buf, 20); // version made by
+        push_u16(&mut buf, 20); // version needed
+        push_u16(&mut buf, 0); // flags
+        push_u16(&mut buf, 0); // method
+        push_u16(&mut buf, 0); // mod time
+        push_u16(&mut buf, 0); // mod date
+        push_u32(&mut buf, 0); // crc32
+        push_u32(&mut buf, a_data.len() as u32);
+        push_u32(&mut buf, a_data.len() as u32);
+        push_u16(&mut buf, a_name.len() as u16);
+        push_u16(&mut buf, 0); // extra len
+        push_u16(&mut buf, 0); // comment len
+        push_u16(&mut buf, 0); // disk number start
+        push_u16(&mut buf, 0); // internal attrs
+        push_u32(&mut buf, 0); // external attrs
+        push_u32(&mut buf, a_local_offset as u32);
+        buf.extend_from_slice(a_name);
+
+        push_u32(&mut buf, CENTRAL_DIR_SIGNATURE);
+        push_u16(&mut buf, 20);
+        push_u16(&mut buf, 20);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u32(&mut buf, 0);
+        push_u32(&mut buf, 0);
+        push_u32(&mut buf, 0);
+        push_u16(&mut buf, empty_name.len() as u16);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u16(&mut buf, 0);
+        push_u32(&mut buf, 0);
+        push_u32(&mut buf, empty_local_offset as u32);
+        buf.extend_from_slice(empty_name);
+
+        let cd_size = buf.len() - cd_offset;
+
+        push_u32(&mut buf, EOCD_SIGNATURE);
+        push_u16(&mut buf, 0); // disk number
+        push_u16(&mut buf, 0); // disk with cd start
+        push_u16(&mut buf, 2); // entries on this disk
+        push_u16(&mut buf, 2); // total entries
+        push_u32(&mut buf, cd_size as u32);
+        push_u32(&mut buf, cd_offset as u32);
+        push_u16(&mut buf, 0); // comment length
+
+        buf
+    }
+
+    fn no_retry() -> RetryPolicy {
+        RetryPolicy { max_retries: 0, base_delay: Duration::from_millis(1) }
+    }
+
+    #[tokio::test]
+    async fn locate_member_finds_stored_file() {
+        let data = build_test_zip();
+        let size = data.len();
+        let store = InMemoryStore { data };
+
+        let location = locate_member(&store, "bucket", "key", size, "a.txt", &no_retry()).await.unwrap();
+        assert_eq!(location.method, 0);
+        let (start, end) = location.range.expect("a.txt has data");
+        assert_eq!(end - start + 1, 2);
+    }
+
+    #[tokio::test]
+    async fn locate_member_returns_no_range_for_zero_length_member() {
+        let data = build_test_zip();
+        let size = data.len();
+        let store = InMemoryStore { data };
+
+        let location = locate_member(&store, "bucket", "key", size, "empty.bin", &no_retry()).await.unwrap();
+        assert_eq!(location.range, None);
+    }
+
+    #[tokio::test]
+    async fn locate_member_errors_for_missing_member() {
+        let data = build_test_zip();
+        let size = data.len();
+        let store = InMemoryStore { data };
+
+        let result = locate_member(&store, "bucket", "key", size, "missing.txt", &no_retry()).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn decompress_member_passes_through_stored_bytes() {
+        let data = Bytes::from_static(b"hi");
+        let out = decompress_member(data.clone(), 0).unwrap();
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn decompress_member_rejects_unsupported_method() {
+        assert!(decompress_member(Bytes::from_static(b"x"), 99).is_err());
+    }
+
+    #[test]
+    fn read_u16_errors_instead_of_panicking_on_truncated_buffer() {
+        assert!(read_u16(&[0u8; 1], 0).is_err());
+    }
+
+    #[test]
+    fn read_u32_errors_instead_of_panicking_on_truncated_buffer() {
+        assert!(read_u32(&[0u8; 3], 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn locate_member_errors_instead_of_panicking_on_truncated_central_directory_entry() {
+        // A central directory entry whose declared name_len reaches past the fetched bytes:
+        // the signature and fixed fields are present, but the name is truncated.
+        let mut data = build_test_zip();
+        let cd_offset = data.len();
+        push_u32(&mut data, CENTRAL_DIR_SIGNATURE);
+        push_u16(&mut data, 20); // version made by
+        push_u16(&mut data, 20); // version needed
+        push_u16(&mut data, 0); // flags
+        push_u16(&mut data, 0); // method
+        push_u16(&mut data, 0); // mod time
+        push_u16(&mut data, 0); // mod date
+        push_u32(&mut data, 0); // crc32
+        push_u32(&mut data, 0); // compressed size
+        push_u32(&mut data, 0); // uncompressed size
+        push_u16(&mut data, 100); // name length: far longer than what follows
+        push_u16(&mut data, 0); // extra len
+        push_u16(&mut data, 0); // comment len
+        push_u16(&mut data, 0); // disk number start
+        push_u16(&mut data, 0); // internal attrs
+        push_u32(&mut data, 0); // external attrs
+        push_u32(&mut data, 0); // relative offset of local header
+        data.extend_from_slice(b"short");
+        let cd_size = data.len() - cd_offset;
+
+        push_u32(&mut data, EOCD_SIGNATURE);
+        push_u16(&mut data, 0);
+        push_u16(&mut data, 0);
+        push_u16(&mut data, 1);
+        push_u16(&mut data, 1);
+        push_u32(&mut data, cd_size as u32);
+        push_u32(&mut data, cd_offset as u32);
+        push_u16(&mut data, 0);
+
+        let size = data.len();
+        let store = InMemoryStore { data };
+        let result = locate_member(&store, "bucket", "key", size, "short", &no_retry()).await;
+        assert!(result.is_err());
+    }
+}